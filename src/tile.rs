@@ -1,6 +1,8 @@
-use core::{fmt, ops::Deref};
+use std::collections::BTreeMap;
 
-use super::tlog::Coordinate;
+use core::{fmt, marker::PhantomData, ops::Deref};
+
+use super::tlog::{self, Coordinate, Hash, ReadHash};
 
 /// A description of a transparency log tile.
 ///
@@ -37,15 +39,15 @@ pub struct Tile {
     /// Level of the tile in `-1..=63`.
     level: isize,
     /// Number within the level in `0..`.
-    n: usize,
+    n: u64,
     /// Width of the tile in `1..=2^height`.
-    width: usize,
+    width: u64,
 }
 
 impl Tile {
     /// Creates a `Tile`, returning `None` if any of the
     /// invariants are violated.
-    pub const fn new(height: usize, level: isize, n: usize, width: usize) -> Option<Self> {
+    pub const fn new(height: usize, level: isize, n: u64, width: u64) -> Option<Self> {
         if height > 30 {
             return None;
         }
@@ -80,14 +82,14 @@ impl Tile {
     /// The number within the level.
     ///
     /// Invariant: the result is in `0..`.
-    pub const fn number(&self) -> usize {
+    pub const fn number(&self) -> u64 {
         self.n
     }
 
     /// The width of the tile.
     ///
     /// Invariant: the result is in `1..=2^height`.
-    pub const fn width(&self) -> usize {
+    pub const fn width(&self) -> u64 {
         self.width
     }
 
@@ -100,20 +102,22 @@ impl Tile {
     /// least width storing the given hash storage index.
     ///
     /// Returns `None` if `height` is out of range.
-    pub fn for_index(height: usize, index: usize) -> Option<Self> {
+    pub fn for_index(height: usize, index: u64) -> Option<Self> {
         if height == 0 || height > 30 {
             return None;
         };
         let Coordinate { mut level, mut n } = Coordinate::split_stored_hash_index(index);
 
+        // This cannot wrap since `height` is in 0..=30 (checked above).
+        #[allow(clippy::cast_possible_wrap)]
+        let height_isize = height as isize;
+
         let mut tile = Tile {
             height,
-            // This cannot wrap since `height` is in 0..=30.
-            #[allow(clippy::cast_possible_wrap)]
-            level: level / (height as isize),
+            level: level / height_isize,
             ..Default::default()
         };
-        level -= tile.level * (height as isize);
+        level -= tile.level * height_isize;
         tile.n = n << level >> tile.height;
         n -= tile.n << tile.height >> level;
         tile.width = (n + 1) << level;
@@ -121,20 +125,143 @@ impl Tile {
         Some(tile)
     }
 
-    ///// Returns the tile's `k`th parent for a tree size of `n`.
-    // fn parent(mut self, k: usize, n: usize) -> Self {
-    //     self.level += k as isize;
-    //     self.n >>= k * self.height;
-    //     self.width = 1 << self.height;
-    //     let max = n >> ((self.level as usize) * self.height);
-    //     if self.n << (self.height + self.width) >= max {
-    //         if self.n << self.height >= max {
-    //             return Self::default();
-    //         }
-    //         self.width = max - (self.n << self.height);
-    //     }
-    //     self
-    // }
+    /// Parses a tile coordinate path of the form `tile/H/L/NNN[.p/W]`,
+    /// the inverse of [`Tile`]'s `Display` impl.
+    ///
+    /// Returns `None` if `path` is not a valid tile path, including
+    /// if the decoded `height`, `level`, or `width` violate the
+    /// invariants enforced by [`Tile::new`].
+    pub fn parse_path(path: &str) -> Option<Self> {
+        let mut segs = path.split('/');
+        if segs.next()? != "tile" {
+            return None;
+        }
+        let height: usize = segs.next()?.parse().ok()?;
+        let level: isize = match segs.next()? {
+            "data" => -1,
+            s => s.parse::<usize>().ok()?.try_into().ok()?,
+        };
+
+        let rest: Vec<&str> = segs.collect();
+        if rest.is_empty() {
+            return None;
+        }
+
+        // The `.p/W` suffix, when present, splits the final `NNN`
+        // group from its width: the group itself carries the `.p`
+        // marker and the width follows as its own path element.
+        let (n_groups, n_last, width): (&[&str], &str, u64) = match rest.as_slice() {
+            [init @ .., partial, last] if partial.strip_suffix(".p").is_some() => {
+                let n_last = partial.strip_suffix(".p")?;
+                let width: u64 = last.parse().ok()?;
+                (init, n_last, width)
+            }
+            [init @ .., last] => (init, last, 1u64 << height),
+            [] => return None,
+        };
+
+        let mut n: u64 = 0;
+        for group in n_groups {
+            let digits = group.strip_prefix('x')?;
+            n = n.checked_mul(1000)?.checked_add(parse_n_group(digits)?)?;
+        }
+        n = n.checked_mul(1000)?.checked_add(parse_n_group(n_last)?)?;
+
+        Self::new(height, level, n, width)
+    }
+
+    /// Returns this tile's `k`th ancestor: the tile `k` levels above
+    /// this one, with its width clamped to what a tree of
+    /// `tree_size` records actually needs.
+    ///
+    /// Returns `None` if the ancestor's level is out of range, or if
+    /// `tree_size` is small enough that the ancestor's position
+    /// falls entirely outside the tree (it isn't needed at all).
+    pub fn parent(mut self, k: usize, tree_size: u64) -> Option<Self> {
+        let level = self.level.checked_add(isize::try_from(k).ok()?)?;
+        if !(0..=63).contains(&level) {
+            return None;
+        }
+        let shift = u32::try_from(k.checked_mul(self.height)?).ok()?;
+        // `u64::checked_shr` rejects shifts >= 64 outright, but Go's
+        // (and this function's) unsigned shift semantics want those
+        // to saturate to 0, not bail out: a tile can still have a
+        // perfectly good ancestor whose `n` is 0 even when the shift
+        // needed to reach it overflows a 64-bit shift amount.
+        self.n = if shift >= u64::BITS { 0 } else { self.n >> shift };
+        self.level = level;
+        self.width = 1 << self.height;
+
+        #[allow(clippy::cast_sign_loss)] // level is in 0..=63, checked above
+        let level_shift = u32::try_from((level as usize).checked_mul(self.height)?).ok()?;
+        let max = if level_shift >= u64::BITS { 0 } else { tree_size >> level_shift };
+        if (self.n << self.height) + self.width >= max {
+            if self.n << self.height >= max {
+                return None;
+            }
+            self.width = max - (self.n << self.height);
+        }
+        Self::new(self.height, self.level, self.n, self.width)
+    }
+
+    /// Enumerates every tile of the given `height` needed to
+    /// compute the root of, and serve records from, a log of
+    /// `tree_size` records, starting from scratch.
+    ///
+    /// This is [`Tiles::new`]'s `(0, tree_size)` case, but derived by
+    /// climbing from the level-0 (record hash) tiles with [`Self::parent`]
+    /// instead of diffing two tree sizes directly. A level's tiles
+    /// don't climb in lockstep: several sibling tiles can share the
+    /// same parent (or have none, if they fall past the last complete
+    /// pair), so each level is rebuilt by climbing every tile from the
+    /// level below and deduplicating, rather than by following a
+    /// single rightmost tile up the tree.
+    pub fn tiles_for_size(height: usize, tree_size: u64) -> Option<Tiles> {
+        if height == 0 || height > 30 {
+            return None;
+        }
+        if tree_size == 0 {
+            return Some(Tiles::default());
+        }
+
+        let n0 = (tree_size - 1) >> height;
+        let width0 = tree_size - (n0 << height);
+        let mut level = Vec::new();
+        for n in 0..n0 {
+            level.push(Self::new(height, 0, n, 1 << height)?);
+        }
+        level.push(Self::new(height, 0, n0, width0)?);
+
+        let mut tiles = level.clone();
+        while let Some(parents) = Self::parents(&level, tree_size) {
+            tiles.extend_from_slice(&parents);
+            level = parents;
+        }
+
+        Some(Tiles {
+            tiles: tiles.into_boxed_slice(),
+        })
+    }
+
+    /// Returns the distinct parents of every tile in `level`, in
+    /// increasing order, or `None` if none of them have a parent
+    /// (i.e. `level` is the topmost level for this `tree_size`).
+    fn parents(level: &[Self], tree_size: u64) -> Option<Vec<Self>> {
+        let mut parents: Vec<Self> = Vec::new();
+        for tile in level {
+            let Some(parent) = tile.parent(1, tree_size) else {
+                continue;
+            };
+            if parents.last().map(Self::number) != Some(parent.number()) {
+                parents.push(parent);
+            }
+        }
+        if parents.is_empty() {
+            None
+        } else {
+            Some(parents)
+        }
+    }
 }
 
 impl fmt::Display for Tile {
@@ -153,7 +280,7 @@ impl fmt::Display for Tile {
             write!(f, "{level}/")?;
         }
 
-        if let Some((first, last)) = format(&mut [0usize; 7], n).split_last() {
+        if let Some((first, last)) = format(&mut [0u64; 7], n).split_last() {
             for v in last {
                 write!(f, "x{v:03}/")?;
             }
@@ -167,8 +294,16 @@ impl fmt::Display for Tile {
     }
 }
 
-fn format(buf: &mut [usize; 7], mut n: usize) -> &[usize] {
-    const PATH_BASE: usize = 1000;
+/// Parses a single 3-digit `NNN` path group, as emitted by [`format`].
+fn parse_n_group(s: &str) -> Option<u64> {
+    if s.len() != 3 || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+fn format(buf: &mut [u64; 7], mut n: u64) -> &[u64] {
+    const PATH_BASE: u64 = 1000;
 
     for (i, v) in buf.iter_mut().enumerate().rev() {
         *v = n % PATH_BASE;
@@ -187,12 +322,17 @@ pub struct Tiles {
 }
 
 impl Tiles {
-    pub fn new(height: usize, old_tree_size: usize, new_tree_size: usize) -> Option<Self> {
+    pub fn new(height: usize, old_tree_size: u64, new_tree_size: u64) -> Option<Self> {
         if height == 0 || height > 30 {
             return None;
         };
         let mut tiles = Vec::new();
         for level in 0..=63 {
+            // This cannot wrap: `level` only ever ranges over the
+            // loop bound `0..=63` above.
+            #[allow(clippy::cast_possible_wrap)]
+            let level_isize = level as isize;
+
             if new_tree_size >> (height * level) == 0 {
                 break;
             }
@@ -203,13 +343,13 @@ impl Tiles {
             }
             let mut n = old_n >> height;
             while n < new_n >> height {
-                tiles.push(Tile::new(height, level as isize, n, 1 << height)?);
+                tiles.push(Tile::new(height, level_isize, n, 1 << height)?);
                 n += 1;
             }
             let n = new_n >> height;
             let width = new_n - (n << height);
             if width > 0 {
-                tiles.push(Tile::new(height, level as isize, n, width)?);
+                tiles.push(Tile::new(height, level_isize, n, width)?);
             }
         }
         Some(Self {
@@ -226,6 +366,179 @@ impl Deref for Tiles {
     }
 }
 
+/// A source of tile payloads, for use with [`TileHashReader`].
+///
+/// Implementations fetch the raw bytes of tiles, typically from a
+/// log server or a local cache, without any knowledge of how those
+/// bytes are verified.
+pub trait TileReader {
+    /// The error returned when a tile cannot be read.
+    type Error;
+
+    /// The height of the tiles this reader serves.
+    fn height(&self) -> usize;
+
+    /// Reads the byte payloads of `tiles`, in the same order.
+    fn read_tiles(&self, tiles: &[Tile]) -> Result<Vec<Vec<u8>>, Self::Error>;
+}
+
+/// An error returned by [`TileHashReader`].
+#[derive(Clone, Debug)]
+pub enum TileHashReaderError<E> {
+    /// The underlying [`TileReader`] failed to read one or more tiles.
+    Tile(E),
+    /// A tile's contents could not be verified against the trusted root.
+    Verification,
+}
+
+/// Parses a tile's byte payload into its `width` leaf hashes.
+///
+/// Returns `None` if `payload` is not exactly `width*32` bytes.
+fn tile_leaves(tile: Tile, payload: &[u8]) -> Option<Vec<Hash>> {
+    let want = tile.width().checked_mul(32)?;
+    if u64::try_from(payload.len()).ok()? != want {
+        return None;
+    }
+    payload
+        .chunks_exact(32)
+        .map(|chunk| <[u8; 32]>::try_from(chunk).ok().map(Hash::from))
+        .collect()
+}
+
+/// A [`ReadHash`] implementation that reads whole tiles through a
+/// [`TileReader`] and verifies each one against a trusted root
+/// before returning any of its hashes.
+///
+/// This is what a downloader should use: log servers only serve
+/// whole tiles, and a malicious or buggy server must not be able to
+/// slip in a hash that is inconsistent with the tree it claims to
+/// be part of.
+///
+/// `H` is the [`tlog::Hasher`] the log was built with; it defaults to
+/// [`tlog::Rfc6962Sha256`], the hasher this crate has always used.
+/// Rust can't infer a defaulted parameter from nothing, so a call to
+/// [`Self::new`] needs a hint even when using the default, e.g.
+/// `TileHashReader::<_>::new(...)`.
+pub struct TileHashReader<T, H = tlog::Rfc6962Sha256> {
+    tree_size: u64,
+    root: Hash,
+    reader: T,
+    _hasher: PhantomData<H>,
+}
+
+impl<T: TileReader, H: tlog::Hasher> TileHashReader<T, H> {
+    /// Creates a reader that verifies tiles against `root`, the
+    /// known hash of the tree of size `tree_size`.
+    pub const fn new(tree_size: u64, root: Hash, reader: T) -> Self {
+        Self {
+            tree_size,
+            root,
+            reader,
+            _hasher: PhantomData,
+        }
+    }
+
+    /// Reads the hashes at `indices` by fetching the tiles that
+    /// cover them, without verifying them against the trusted root.
+    ///
+    /// [`Self::read_hashes`] verifies every hash it returns; this is
+    /// the unverified primitive it and [`Self::verify_hash`] are
+    /// built from, since a sibling hash consumed while building an
+    /// inclusion proof is validated by that proof's own comparison
+    /// against `self.root`, not by re-deriving its own proof (doing
+    /// so recurses forever for any pair of indices that are each
+    /// other's sibling).
+    fn read_leaves(&self, indices: &[u64]) -> Result<BTreeMap<u64, Hash>, TileHashReaderError<T::Error>> {
+        let height = self.reader.height();
+
+        // Group the requested indices by the (minimal) tile that
+        // covers them, so each distinct tile payload is fetched once.
+        let mut by_tile: BTreeMap<Tile, Vec<u64>> = BTreeMap::new();
+        for &index in indices {
+            let tile = Tile::for_index(height, index).ok_or(TileHashReaderError::Verification)?;
+            by_tile.entry(tile).or_default().push(index);
+        }
+
+        let tiles: Vec<Tile> = by_tile.keys().copied().collect();
+        let payloads = self.reader.read_tiles(&tiles).map_err(TileHashReaderError::Tile)?;
+        if payloads.len() != tiles.len() {
+            return Err(TileHashReaderError::Verification);
+        }
+
+        let mut found = BTreeMap::new();
+        for (tile, payload) in tiles.iter().zip(payloads) {
+            let leaves = tile_leaves(*tile, &payload).ok_or(TileHashReaderError::Verification)?;
+            for &index in by_tile.get(tile).into_iter().flatten() {
+                // `Tile::for_index` picks the narrowest tile that
+                // still covers `index`, so `index`'s hash is the
+                // combination of exactly the tile's trailing
+                // `2^d` leaves, where `d` is how far above the
+                // tile's own base level `index`'s coordinate sits.
+                let coord = Coordinate::split_stored_hash_index(index);
+                #[allow(clippy::cast_possible_wrap)] // height is in 1..=30
+                let base_level = tile.level() * height as isize;
+                #[allow(clippy::cast_sign_loss)] // base_level <= coord.level by construction
+                let d = (coord.level - base_level) as usize;
+                let span = 1usize << d;
+                let start = leaves.len().checked_sub(span).ok_or(TileHashReaderError::Verification)?;
+                let sub_leaves = leaves.get(start..).ok_or(TileHashReaderError::Verification)?;
+                found.insert(index, tlog::merkle_hash::<H>(sub_leaves));
+            }
+        }
+        Ok(found)
+    }
+
+    /// Verifies that `hash`, the hash stored at `index`, is
+    /// consistent with the trusted root: either `index` names the
+    /// root itself, or `hash` must check out against `self.root`
+    /// via an inclusion proof for the complete subtree it names.
+    fn verify_hash(&self, index: u64, hash: Hash) -> Result<(), TileHashReaderError<T::Error>> {
+        let coord = Coordinate::split_stored_hash_index(index);
+        #[allow(clippy::cast_sign_loss)] // hash coordinates always have level >= 0
+        let level = coord.level as usize;
+        let lo = coord.n << level;
+        let hi = lo + (1 << level);
+        if hi > self.tree_size {
+            return Err(TileHashReaderError::Verification);
+        }
+        if lo == 0 && hi == self.tree_size {
+            return if hash == self.root {
+                Ok(())
+            } else {
+                Err(TileHashReaderError::Verification)
+            };
+        }
+
+        let mut need = Vec::new();
+        tlog::subtree_proof_indices(0, self.tree_size, lo, hi, &mut need);
+        let hashes = self.read_leaves(&need)?;
+        let proof = tlog::subtree_proof::<H>(0, self.tree_size, lo, hi, &hashes);
+        if tlog::run_subtree_proof::<H>(&proof, 0, self.tree_size, lo, hi, hash) == Some(self.root) {
+            Ok(())
+        } else {
+            Err(TileHashReaderError::Verification)
+        }
+    }
+}
+
+impl<T: TileReader, H: tlog::Hasher> ReadHash for TileHashReader<T, H> {
+    type Error = TileHashReaderError<T::Error>;
+
+    fn read_hashes(&self, indices: &[u64]) -> Result<impl Iterator<Item = Hash>, Self::Error> {
+        let found = self.read_leaves(indices)?;
+        for (&index, &hash) in &found {
+            self.verify_hash(index, hash)?;
+        }
+
+        let hashes: Vec<Hash> = indices
+            .iter()
+            .map(|i| found.get(i).copied())
+            .collect::<Option<_>>()
+            .ok_or(TileHashReaderError::Verification)?;
+        Ok(hashes.into_iter())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -242,6 +555,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::unwrap_used)] // test-only, all cases below are known-valid
     fn test_tiles_new() {
         let cases = [
             (1, 1, 0),
@@ -258,6 +572,141 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_tile_parent() {
+        // (tile, k, tree_size, want)
+        let cases = [
+            (tile!(2, 0, 0, 4), 1, 16, Some(tile!(2, 1, 0, 4))),
+            (tile!(2, 0, 0, 4), 2, 16, Some(tile!(2, 2, 0, 1))),
+            (tile!(2, 0, 0, 4), 3, 16, None),
+            // A trailing partial tile with no sibling yet has no
+            // parent: record 4 hasn't been combined into a level-1
+            // node because record 5 hasn't arrived.
+            (tile!(1, 0, 2, 1), 1, 5, None),
+            // Once the tree is big enough for that pair to complete,
+            // the parent exists and is itself partial.
+            (tile!(1, 0, 2, 1), 1, 6, Some(tile!(1, 1, 1, 1))),
+            // `k * height` (70) exceeds 64, the width of the shift
+            // this climbs `n` by: the ancestor still exists (its `n`
+            // is just 0), so this must not be confused with "no such
+            // tile" the way a naively `checked_shr`'d shift would.
+            (tile!(10, -1, 0, 1), 7, 1u64 << 63, Some(tile!(10, 6, 0, 8))),
+        ];
+        for (tile, k, tree_size, want) in cases {
+            let got = tile.parent(k, tree_size);
+            assert_eq!(got, want, "({tile:?}, {k}, {tree_size})");
+        }
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)] // test-only, all cases below are known-valid
+    fn test_tiles_for_size() {
+        // Mirrors `test_tiles_new`'s table: `Tile::tiles_for_size(height,
+        // new)` should always agree with `Tiles::new(height, 0, new)`.
+        let cases = [1, 1023, 1024, 1025, 1030, 2000, 10000, 49516586];
+        for new in cases {
+            let got = Tile::tiles_for_size(10, new).unwrap();
+            let want = Tiles::new(10, 0, new).unwrap();
+            assert_eq!(&*got, &*want, "(10, {new})");
+        }
+        assert_eq!(Tile::tiles_for_size(10, 0).unwrap().len(), 0);
+        assert!(Tile::tiles_for_size(0, 10).is_none());
+        assert!(Tile::tiles_for_size(31, 10).is_none());
+    }
+
+    use tlog::test_support::{build_hashes, VecHashReader, XorHasher};
+
+    // A `TileReader` that serves prefixes of full, pre-stored tiles
+    // from an in-memory map, as a real log server would.
+    struct MapTileReader {
+        height: usize,
+        payloads: BTreeMap<(isize, u64), Vec<u8>>,
+        corrupt: Option<(isize, u64)>,
+    }
+
+    impl TileReader for MapTileReader {
+        type Error = String;
+        fn height(&self) -> usize {
+            self.height
+        }
+        fn read_tiles(&self, tiles: &[Tile]) -> Result<Vec<Vec<u8>>, Self::Error> {
+            tiles
+                .iter()
+                .map(|t| {
+                    let key = (t.level(), t.number());
+                    let full = self.payloads.get(&key).ok_or("missing tile")?;
+                    let want = usize::try_from(t.width().checked_mul(32).ok_or("width overflow")?)
+                        .map_err(|_| "width exceeds stored tile")?;
+                    let mut payload = full.get(..want).ok_or("width exceeds stored tile")?.to_vec();
+                    if Some(key) == self.corrupt {
+                        if let Some(b) = payload.first_mut() {
+                            *b ^= 0xff;
+                        }
+                    }
+                    Ok(payload)
+                })
+                .collect()
+        }
+    }
+
+    #[allow(clippy::indexing_slicing)] // test-only incremental builder, indices are in-bounds by construction
+    #[allow(clippy::cast_possible_wrap)] // test-only, tile.level()*height fits in isize
+    #[allow(clippy::unwrap_used)] // test-only, (height, 0, n) is always a valid range
+    fn build_reader<H: tlog::Hasher>(height: usize, n: u64, corrupt: Option<(isize, u64)>) -> MapTileReader {
+        let hashes = build_hashes::<H>(n);
+        let mut payloads = BTreeMap::new();
+        for tile in Tiles::new(height, 0, n).unwrap().iter() {
+            let level = tile.level() * height as isize;
+            let base = tile.number() << tile.height();
+            let mut payload = Vec::new();
+            for i in 0..tile.width() {
+                payload.extend_from_slice(hashes[Coordinate { level, n: base + i }.stored_hash_index() as usize].as_ref());
+            }
+            payloads.insert((tile.level(), tile.number()), payload);
+        }
+        MapTileReader { height, payloads, corrupt }
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)] // test-only, n and root are known-valid by construction
+    fn test_tile_hash_reader() {
+        use tlog::{check_record, prove_record, record_hash, tree_hash, Rfc6962Sha256};
+
+        let n = 19;
+        let flat = VecHashReader(build_hashes::<Rfc6962Sha256>(n));
+        let root = tree_hash(n, &flat).unwrap();
+
+        let thr = TileHashReader::<_>::new(n, root, build_reader::<Rfc6962Sha256>(2, n, None));
+        assert_eq!(tree_hash(n, &thr).unwrap(), root);
+        for i in 0..n {
+            let proof = prove_record(n, i, &thr).unwrap();
+            assert!(check_record(&proof, n, i, record_hash(&i.to_le_bytes()), root));
+        }
+
+        let bad = TileHashReader::<_>::new(n, root, build_reader::<Rfc6962Sha256>(2, n, Some((0, 0))));
+        assert!(bad.read_hashes(&[0]).is_err());
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)] // test-only, n and root are known-valid by construction
+    fn test_tile_hash_reader_custom_hasher() {
+        use tlog::{check_record_with, prove_record_with, tree_hash_with, Hasher};
+
+        let n = 19;
+        let flat = VecHashReader(build_hashes::<XorHasher>(n));
+        let root = tree_hash_with::<XorHasher, _>(n, &flat).unwrap();
+
+        let thr = TileHashReader::<_, XorHasher>::new(n, root, build_reader::<XorHasher>(2, n, None));
+        assert_eq!(tree_hash_with::<XorHasher, _>(n, &thr).unwrap(), root);
+        for i in 0..n {
+            let proof = prove_record_with::<XorHasher, _>(n, i, &thr).unwrap();
+            assert!(check_record_with::<XorHasher>(&proof, n, i, XorHasher::hash_record(&i.to_le_bytes()), root));
+        }
+
+        let bad = TileHashReader::<_, XorHasher>::new(n, root, build_reader::<XorHasher>(2, n, Some((0, 0))));
+        assert!(bad.read_hashes(&[0]).is_err());
+    }
+
     #[test]
     fn test_tile_paths() {
         let cases = [
@@ -274,8 +723,10 @@ mod tests {
             if tile.height > 0 {
                 let got = tile.to_string();
                 assert_eq!(got, path, "{tile:?}");
+                assert_eq!(Tile::parse_path(path), Some(tile), "{path}");
+            } else {
+                assert_eq!(Tile::parse_path(path), None, "{path}");
             }
-            // TODO: parse
         }
     }
 }
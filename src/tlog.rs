@@ -1,6 +1,8 @@
+use std::collections::BTreeMap;
+
 use sha2::{Digest, Sha256};
 
-const fn maxpow2(n: usize) -> (usize, isize) {
+pub(crate) const fn maxpow2(n: u64) -> (u64, isize) {
     let mut l = 0;
     while 1 << (l + 1) < n {
         l += 1;
@@ -8,7 +10,7 @@ const fn maxpow2(n: usize) -> (usize, isize) {
     (1 << l, l)
 }
 
-#[derive(Copy, Clone, Debug, Default)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub struct Hash([u8; 32]);
 
 impl AsRef<[u8]> for Hash {
@@ -17,25 +19,71 @@ impl AsRef<[u8]> for Hash {
     }
 }
 
-/// Returns the content hash for record data.
+impl From<[u8; 32]> for Hash {
+    fn from(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+/// A pluggable digest for a transparency log.
+///
+/// This centralizes the domain-separation prefixes that tell a leaf
+/// hash apart from an interior node hash, so a log that uses a
+/// different digest (e.g. SHA-512/256, or a truncated hash) can reuse
+/// all of this crate's tiling and proof logic instead of forking it.
+/// [`Rfc6962Sha256`] is the default and reproduces this crate's
+/// original, hardcoded behavior.
+pub trait Hasher {
+    /// Returns the hash of the empty tree.
+    fn empty_root() -> Hash;
+
+    /// Returns the content hash for record data.
+    fn hash_record(data: &[u8]) -> Hash;
+
+    /// Returns the hash for an interior tree node.
+    fn hash_children(left: &Hash, right: &Hash) -> Hash;
+}
+
+/// The RFC 6962 hash function: SHA-256 with the `0x00`/`0x01`
+/// leaf/interior domain-separation prefixes.
+///
+/// <https://tools.ietf.org/html/rfc6962#section-2.1>
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Rfc6962Sha256;
+
+impl Hasher for Rfc6962Sha256 {
+    fn empty_root() -> Hash {
+        Hash::default()
+    }
+
+    fn hash_record(data: &[u8]) -> Hash {
+        // SHA256(0x00 || data)
+        let mut h = Sha256::new();
+        h.update(&[0x00]);
+        h.update(data);
+        Hash(h.finalize().into())
+    }
+
+    fn hash_children(left: &Hash, right: &Hash) -> Hash {
+        // SHA256(0x01 || left || right)
+        let mut h = Sha256::new();
+        h.update(&[0x01]);
+        h.update(left);
+        h.update(right);
+        Hash(h.finalize().into())
+    }
+}
+
+/// Returns the content hash for record data, using the default
+/// ([`Rfc6962Sha256`]) hasher.
 pub fn record_hash(data: &[u8]) -> Hash {
-    // SHA256(0x00 || data)
-    // https://tools.ietf.org/html/rfc6962#section-2.1
-    let mut h = Sha256::new();
-    h.update(&[0x00]);
-    h.update(data);
-    Hash(h.finalize().into())
+    Rfc6962Sha256::hash_record(data)
 }
 
-/// Returns the hash for an interior tree node.
+/// Returns the hash for an interior tree node, using the default
+/// ([`Rfc6962Sha256`]) hasher.
 pub fn node_hash(left: &Hash, right: &Hash) -> Hash {
-    // SHA256(0x01 || left || right)
-    // https://tools.ietf.org/html/rfc6962#section-2.1
-    let mut h = Sha256::new();
-    h.update(&[0x01]);
-    h.update(left);
-    h.update(right);
-    Hash(h.finalize().into())
+    Rfc6962Sha256::hash_children(left, right)
 }
 
 /// The coordinate of a tile.
@@ -44,7 +92,7 @@ pub struct Coordinate {
     /// The tile's level.
     pub level: isize,
     /// The tile's number within the level.
-    pub n: usize,
+    pub n: u64,
 }
 
 impl Coordinate {
@@ -59,7 +107,7 @@ impl Coordinate {
     /// );
     /// assert_eq!(got, want);
     /// ```
-    pub fn split_stored_hash_index(index: usize) -> Self {
+    pub fn split_stored_hash_index(index: u64) -> Self {
         // Determine level 0 record before index.
         //
         // Given `stored_hash_index(0, n)` < 2*n, the `n` we want
@@ -71,7 +119,7 @@ impl Coordinate {
         loop {
             // Each new record n adds 1 + trailingZeros(n)
             // hashes.
-            let x = index_n + 1 + (n + 1).trailing_zeros() as usize;
+            let x = index_n + 1 + u64::from((n + 1).trailing_zeros());
             if x > index {
                 break;
             }
@@ -80,7 +128,12 @@ impl Coordinate {
         }
         // The hash we want was committed with record n, meaning
         // it is one of (0, n), (1, n/2), (2, n/4), ...
-        let level = (index - index_n) as isize;
+        //
+        // `index_n <= index` is the loop invariant above, so this
+        // never underflows; saturate the cast to `isize` rather than
+        // risk silently wrapping for the same reason the rest of
+        // this module saturates instead of wrapping.
+        let level = isize::try_from(index - index_n).unwrap_or(isize::MAX);
         Self {
             level,
             n: n >> level,
@@ -93,14 +146,14 @@ impl Coordinate {
     /// Hash storage implementations that store hashes in
     /// sequential storage can use this function to compute where
     /// to read or write a given hash.
-    pub fn stored_hash_index(&self) -> usize {
+    pub fn stored_hash_index(&self) -> u64 {
         stored_hash_index(self.level, self.n)
     }
 }
 
 /// Returns the number of stored hashes that are expected for
 /// a tree with `n` records.
-pub fn stored_hash_count(n: usize) -> usize {
+pub fn stored_hash_count(n: u64) -> u64 {
     if n == 0 {
         return 0;
     }
@@ -116,7 +169,7 @@ pub fn stored_hash_count(n: usize) -> usize {
     num_hash
 }
 
-fn stored_hash_index(level: isize, mut n: usize) -> usize {
+fn stored_hash_index(level: isize, mut n: u64) -> u64 {
     debug_assert!(level >= -1);
     debug_assert!(level <= 63);
 
@@ -126,33 +179,70 @@ fn stored_hash_index(level: isize, mut n: usize) -> usize {
     // Work our way down to the level 0 ordering. We'll add
     // back the original level count at the end.
     for _ in (1..=level).rev() {
-        // TODO(eric): overflow
-        n = 2 * n + 1;
+        // A tree with more than `u64::MAX` stored hashes can't
+        // exist in practice (it would need more than `u64::MAX`
+        // records); saturate instead of silently wrapping so an
+        // index derived from an impossible level/n pair is
+        // obviously wrong rather than aliasing a valid one.
+        n = n.saturating_mul(2).saturating_add(1);
     }
 
     // The nth hash for level 0 is written at n+n/2+n/4+...
     // (n/2^i eventually hits zero.)
-    let mut i = 0;
+    let mut i: u64 = 0;
     while n > 0 {
-        i += n;
+        // Same saturation rationale as the climb above: this only
+        // overflows for an `n`/`level` pair that's already
+        // impossible in practice.
+        i = i.saturating_add(n);
         n >>= 1;
     }
-    ((i as isize) + level) as usize
+    // `saturating_add_signed` folds `level` (a small, bounded
+    // offset) in directly, so there's no round trip through `isize`
+    // that could wrap for a saturated `i` near `u64::MAX`; it
+    // saturates at `0`/`u64::MAX` for the same reason the
+    // accumulation above saturates instead of wrapping.
+    #[allow(clippy::cast_possible_truncation)] // level is -1..=63, fits in i64
+    i.saturating_add_signed(level as i64)
 }
 
 pub trait ReadHash {
     type Error;
-    fn read_hashes(indices: &[usize]) -> Result<impl Iterator<Item = Hash>, Self::Error>;
+    /// Reads the hashes stored at `indices`.
+    ///
+    /// `indices` is guaranteed to be sorted and strictly increasing.
+    fn read_hashes(&self, indices: &[u64]) -> Result<impl Iterator<Item = Hash>, Self::Error>;
 }
 
-pub fn tree_hash<R: ReadHash>(n: usize, reader: R) -> Result<Hash, R::Error> {
+/// Computes the Merkle root of the first `n` leaves, as read through
+/// `reader`, using the default ([`Rfc6962Sha256`]) hasher.
+pub fn tree_hash<R: ReadHash>(n: u64, reader: &R) -> Result<Hash, R::Error> {
+    tree_hash_with::<Rfc6962Sha256, R>(n, reader)
+}
+
+/// Computes the Merkle root of the first `n` leaves, as read through
+/// `reader`, using the given [`Hasher`].
+pub fn tree_hash_with<H: Hasher, R: ReadHash>(n: u64, reader: &R) -> Result<Hash, R::Error> {
     if n == 0 {
-        return Ok(Default::default());
+        return Ok(H::empty_root());
     }
-    let indices = sub_tree_index();
+    let indices: Vec<u64> = sub_tree_index(0, n, &[]).collect();
+    let hashes: Vec<Hash> = reader.read_hashes(&indices)?.collect();
+    Ok(fold_hashes_rtl::<H>(hashes.into_iter()))
 }
 
-fn sub_tree_index(mut lo: usize, hi: usize, need: &[usize]) -> impl Iterator<Item = usize> {
+/// Folds complete-subtree hashes right-to-left: the rightmost,
+/// smallest subtree combines first, nesting outward to the left.
+fn fold_hashes_rtl<H: Hasher>(hashes: impl DoubleEndedIterator<Item = Hash>) -> Hash {
+    let mut iter = hashes.rev();
+    let mut acc = iter.next().unwrap_or_default();
+    for h in iter {
+        acc = H::hash_children(&h, &acc);
+    }
+    acc
+}
+
+fn sub_tree_index(mut lo: u64, hi: u64, need: &[u64]) -> impl Iterator<Item = u64> {
     core::iter::from_fn(move || {
         if lo < hi {
             let (k, level) = maxpow2(hi - lo + 1);
@@ -168,9 +258,378 @@ fn sub_tree_index(mut lo: usize, hi: usize, need: &[usize]) -> impl Iterator<Ite
     })
 }
 
+/// A proof that a record is contained in a tree, returned by
+/// [`prove_record`] and checked by [`check_record`].
+pub type RecordProof = Box<[Hash]>;
+
+/// A proof that one tree is a prefix of another (a "consistency
+/// proof"), returned by [`prove_tree`] and checked by [`check_tree`].
+pub type TreeProof = Box<[Hash]>;
+
+/// Proves that the tree of size `t` contains the record with index
+/// `n`, using the default ([`Rfc6962Sha256`]) hasher.
+pub fn prove_record<R: ReadHash>(t: u64, n: u64, reader: &R) -> Result<RecordProof, R::Error> {
+    prove_record_with::<Rfc6962Sha256, R>(t, n, reader)
+}
+
+/// Proves that the tree of size `t` contains the record with index
+/// `n`, using the given [`Hasher`].
+pub fn prove_record_with<H: Hasher, R: ReadHash>(t: u64, n: u64, reader: &R) -> Result<RecordProof, R::Error> {
+    debug_assert!(n < t);
+    let mut indices = Vec::new();
+    leaf_proof_indices(0, t, n, &mut indices);
+    let hashes = read_indices(&indices, reader)?;
+    Ok(leaf_proof::<H>(0, t, n, &hashes).into_boxed_slice())
+}
+
+/// Checks the proof that `leaf_hash` (the hash of record `n`) is
+/// contained in the tree of size `t` with the given `tree_hash` root,
+/// using the default ([`Rfc6962Sha256`]) hasher.
+pub fn check_record(proof: &RecordProof, t: u64, n: u64, leaf_hash: Hash, tree_hash: Hash) -> bool {
+    check_record_with::<Rfc6962Sha256>(proof, t, n, leaf_hash, tree_hash)
+}
+
+/// Checks the proof that `leaf_hash` (the hash of record `n`) is
+/// contained in the tree of size `t` with the given `tree_hash` root,
+/// using the given [`Hasher`].
+pub fn check_record_with<H: Hasher>(proof: &RecordProof, t: u64, n: u64, leaf_hash: Hash, tree_hash: Hash) -> bool {
+    if n >= t {
+        return false;
+    }
+    matches!(run_record_proof::<H>(proof, 0, t, n, leaf_hash), Some(h) if h == tree_hash)
+}
+
+/// Proves that the tree of size `n` is a prefix of the tree of size
+/// `t`, using the default ([`Rfc6962Sha256`]) hasher.
+pub fn prove_tree<R: ReadHash>(t: u64, n: u64, reader: &R) -> Result<TreeProof, R::Error> {
+    prove_tree_with::<Rfc6962Sha256, R>(t, n, reader)
+}
+
+/// Proves that the tree of size `n` is a prefix of the tree of size
+/// `t`, using the given [`Hasher`].
+pub fn prove_tree_with<H: Hasher, R: ReadHash>(t: u64, n: u64, reader: &R) -> Result<TreeProof, R::Error> {
+    debug_assert!(n > 0 && n <= t);
+    let mut indices = Vec::new();
+    tree_proof_indices(0, t, n, &mut indices);
+    let hashes = read_indices(&indices, reader)?;
+    Ok(tree_proof::<H>(0, t, n, &hashes).into_boxed_slice())
+}
+
+/// Checks the proof that the tree of size `n` with root `old_hash` is
+/// a prefix of the tree of size `t` with root `new_hash`, using the
+/// default ([`Rfc6962Sha256`]) hasher.
+pub fn check_tree(proof: &TreeProof, t: u64, n: u64, old_hash: Hash, new_hash: Hash) -> bool {
+    check_tree_with::<Rfc6962Sha256>(proof, t, n, old_hash, new_hash)
+}
+
+/// Checks the proof that the tree of size `n` with root `old_hash` is
+/// a prefix of the tree of size `t` with root `new_hash`, using the
+/// given [`Hasher`].
+pub fn check_tree_with<H: Hasher>(proof: &TreeProof, t: u64, n: u64, old_hash: Hash, new_hash: Hash) -> bool {
+    if n == 0 || n > t {
+        return false;
+    }
+    matches!(
+        run_tree_proof::<H>(proof, 0, t, n, old_hash),
+        Some((oh, th)) if oh == old_hash && th == new_hash
+    )
+}
+
+/// Fetches the hashes needed for the (possibly out-of-order) `indices`
+/// with a single, sorted, deduplicated `reader.read_hashes` call, and
+/// returns them keyed by index for lookup in proof-construction order.
+pub(crate) fn read_indices<R: ReadHash>(indices: &[u64], reader: &R) -> Result<BTreeMap<u64, Hash>, R::Error> {
+    let mut sorted = indices.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    let hashes: Vec<Hash> = reader.read_hashes(&sorted)?.collect();
+    Ok(sorted.into_iter().zip(hashes).collect())
+}
+
+/// Gathers the storage indices of the sibling subtrees needed to
+/// prove that record `n` is a leaf of the tree covering `[lo, hi)`,
+/// starting with the sibling nearest the root.
+fn leaf_proof_indices(lo: u64, hi: u64, n: u64, out: &mut Vec<u64>) {
+    subtree_proof_indices(lo, hi, n, n + 1, out);
+}
+
+/// Builds the record proof for `n` within `[lo, hi)`, from the leaf
+/// upward, using `hashes` gathered by [`leaf_proof_indices`].
+fn leaf_proof<H: Hasher>(lo: u64, hi: u64, n: u64, hashes: &BTreeMap<u64, Hash>) -> Vec<Hash> {
+    subtree_proof::<H>(lo, hi, n, n + 1, hashes)
+}
+
+/// Recomputes the root hash for the leaf proof `proof`, folding
+/// `leaf_hash` up with the sibling hashes from `proof` in reverse
+/// (root-to-leaf) order.
+fn run_record_proof<H: Hasher>(proof: &[Hash], lo: u64, hi: u64, n: u64, leaf_hash: Hash) -> Option<Hash> {
+    run_subtree_proof::<H>(proof, lo, hi, n, n + 1, leaf_hash)
+}
+
+/// Gathers the storage indices of the sibling subtrees needed to
+/// prove that the complete subtree covering `[target_lo, target_hi)`
+/// is contained in the tree covering `[lo, hi)`, starting with the
+/// sibling nearest the root (the reverse of the order in which
+/// [`subtree_proof`] lists the sibling hashes).
+///
+/// This generalizes [`leaf_proof_indices`] from a single leaf `n`
+/// (the range `[n, n+1)`) to an arbitrary aligned range, which is
+/// what [`crate::tile::TileHashReader`] needs to verify a tile's
+/// leaves (themselves already hashes of a subtree) against the
+/// trusted root.
+pub(crate) fn subtree_proof_indices(lo: u64, hi: u64, target_lo: u64, target_hi: u64, out: &mut Vec<u64>) {
+    debug_assert!(lo <= target_lo && target_lo < target_hi && target_hi <= hi);
+    if lo == target_lo && hi == target_hi {
+        return;
+    }
+    let (k, _) = maxpow2(hi - lo);
+    if target_hi <= lo + k {
+        out.extend(sub_tree_index(lo + k, hi, &[]));
+        subtree_proof_indices(lo, lo + k, target_lo, target_hi, out);
+    } else {
+        out.extend(sub_tree_index(lo, lo + k, &[]));
+        subtree_proof_indices(lo + k, hi, target_lo, target_hi, out);
+    }
+}
+
+/// Builds the inclusion proof for the complete subtree covering
+/// `[target_lo, target_hi)` within `[lo, hi)`, using `hashes`
+/// gathered by [`subtree_proof_indices`].
+pub(crate) fn subtree_proof<H: Hasher>(
+    lo: u64,
+    hi: u64,
+    target_lo: u64,
+    target_hi: u64,
+    hashes: &BTreeMap<u64, Hash>,
+) -> Vec<Hash> {
+    debug_assert!(lo <= target_lo && target_lo < target_hi && target_hi <= hi);
+    if lo == target_lo && hi == target_hi {
+        return Vec::new();
+    }
+    let (k, _) = maxpow2(hi - lo);
+    if target_hi <= lo + k {
+        let mut proof = subtree_proof::<H>(lo, lo + k, target_lo, target_hi, hashes);
+        proof.push(hash_sub_tree::<H>(lo + k, hi, hashes));
+        proof
+    } else {
+        let mut proof = subtree_proof::<H>(lo + k, hi, target_lo, target_hi, hashes);
+        proof.push(hash_sub_tree::<H>(lo, lo + k, hashes));
+        proof
+    }
+}
+
+/// Recomputes the root hash of the (possibly partial) subtree
+/// `[lo, hi)` by folding the hashes of the complete subtrees that
+/// make it up right-to-left: the rightmost,
+/// smallest subtrees combine first, nesting outward to the left.
+fn hash_sub_tree<H: Hasher>(lo: u64, hi: u64, hashes: &BTreeMap<u64, Hash>) -> Hash {
+    let parts: Vec<Hash> = sub_tree_index(lo, hi, &[])
+        .map(|i| hashes.get(&i).copied().unwrap_or_default())
+        .collect();
+    fold_hashes_rtl::<H>(parts.into_iter())
+}
+
+/// Recomputes the root hash for the subtree proof `proof`, folding
+/// `target_hash` (the already-known hash of `[target_lo, target_hi)`)
+/// up with the sibling hashes from `proof` in reverse (root-to-leaf)
+/// order.
+pub(crate) fn run_subtree_proof<H: Hasher>(
+    proof: &[Hash],
+    lo: u64,
+    hi: u64,
+    target_lo: u64,
+    target_hi: u64,
+    target_hash: Hash,
+) -> Option<Hash> {
+    debug_assert!(lo <= target_lo && target_lo < target_hi && target_hi <= hi);
+    if lo == target_lo && hi == target_hi {
+        return proof.is_empty().then_some(target_hash);
+    }
+    let (sibling, rest) = proof.split_last()?;
+    let (k, _) = maxpow2(hi - lo);
+    if target_hi <= lo + k {
+        let h = run_subtree_proof::<H>(rest, lo, lo + k, target_lo, target_hi, target_hash)?;
+        Some(H::hash_children(&h, sibling))
+    } else {
+        let h = run_subtree_proof::<H>(rest, lo + k, hi, target_lo, target_hi, target_hash)?;
+        Some(H::hash_children(sibling, &h))
+    }
+}
+
+/// Computes the root of a (possibly partial) Merkle tree over raw
+/// leaf hashes, by recursively splitting at the largest power of two
+/// `< leaves.len()` and combining the two halves with `H`.
+///
+/// Unlike [`hash_sub_tree`], which folds already-complete subtree
+/// hashes read from storage, this combines `leaves` directly and so
+/// works for leaves that have not yet been assigned storage indices,
+/// such as the raw hashes listed in a [`crate::tile::Tile`].
+pub(crate) fn merkle_hash<H: Hasher>(leaves: &[Hash]) -> Hash {
+    match leaves.split_first() {
+        None => H::empty_root(),
+        Some((&first, [])) => first,
+        _ => {
+            // `leaves.len()` always fits comfortably in a tile
+            // (at most `2^30` entries), well within `u64`.
+            let (k, _) = maxpow2(leaves.len() as u64);
+            #[allow(clippy::cast_possible_truncation)] // k < leaves.len(), which is a usize
+            let (left, right) = leaves.split_at(k as usize);
+            H::hash_children(&merkle_hash::<H>(left), &merkle_hash::<H>(right))
+        }
+    }
+}
+
+/// Gathers the storage indices of the sibling subtrees needed to
+/// prove that the tree covering `[0, n)` is a prefix of the tree
+/// covering `[lo, hi)`, starting with the sibling nearest the root.
+fn tree_proof_indices(lo: u64, hi: u64, n: u64, out: &mut Vec<u64>) {
+    debug_assert!(lo < n && n <= hi);
+    if n == hi {
+        if lo != 0 {
+            out.extend(sub_tree_index(lo, hi, &[]));
+        }
+        return;
+    }
+    let (k, _) = maxpow2(hi - lo);
+    if n <= lo + k {
+        out.extend(sub_tree_index(lo + k, hi, &[]));
+        tree_proof_indices(lo, lo + k, n, out);
+    } else {
+        out.extend(sub_tree_index(lo, lo + k, &[]));
+        tree_proof_indices(lo + k, hi, n, out);
+    }
+}
+
+/// Builds the tree (consistency) proof for `n` within `[lo, hi)`,
+/// using `hashes` gathered by [`tree_proof_indices`].
+fn tree_proof<H: Hasher>(lo: u64, hi: u64, n: u64, hashes: &BTreeMap<u64, Hash>) -> Vec<Hash> {
+    debug_assert!(lo < n && n <= hi);
+    if n == hi {
+        return if lo == 0 {
+            Vec::new()
+        } else {
+            vec![hash_sub_tree::<H>(lo, hi, hashes)]
+        };
+    }
+    let (k, _) = maxpow2(hi - lo);
+    if n <= lo + k {
+        let mut proof = tree_proof::<H>(lo, lo + k, n, hashes);
+        proof.push(hash_sub_tree::<H>(lo + k, hi, hashes));
+        proof
+    } else {
+        let mut proof = vec![hash_sub_tree::<H>(lo, lo + k, hashes)];
+        proof.extend(tree_proof::<H>(lo + k, hi, n, hashes));
+        proof
+    }
+}
+
+/// Recomputes `(old_hash, new_hash)` for the tree proof `proof`,
+/// folding the trusted `old_hash` root up with the sibling hashes
+/// from `proof`. At each level the sibling is taken from the end of
+/// `proof` when `n` falls in the left half and from the front when
+/// it falls in the right half, matching where [`tree_proof`] put it.
+fn run_tree_proof<H: Hasher>(proof: &[Hash], lo: u64, hi: u64, n: u64, old: Hash) -> Option<(Hash, Hash)> {
+    debug_assert!(lo < n && n <= hi);
+    if n == hi {
+        return if lo == 0 {
+            proof.is_empty().then_some((old, old))
+        } else {
+            let (&h, rest) = proof.split_first()?;
+            rest.is_empty().then_some((h, h))
+        };
+    }
+    let (k, _) = maxpow2(hi - lo);
+    if n <= lo + k {
+        let (sibling, rest) = proof.split_last()?;
+        let (oh, th) = run_tree_proof::<H>(rest, lo, lo + k, n, old)?;
+        Some((oh, H::hash_children(&th, sibling)))
+    } else {
+        let (sibling, rest) = proof.split_first()?;
+        let (oh, th) = run_tree_proof::<H>(rest, lo + k, hi, n, old)?;
+        Some((H::hash_children(sibling, &oh), H::hash_children(sibling, &th)))
+    }
+}
+
+/// Test-only fixtures shared between this module's tests and
+/// `tile`'s, so both can exercise their generic `H`/[`ReadHash`]
+/// plumbing without each keeping its own copy of the same fixture.
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::{stored_hash_count, stored_hash_index, Hash, Hasher, ReadHash};
+
+    /// A toy [`Hasher`] distinct from [`Rfc6962Sha256`](super::Rfc6962Sha256),
+    /// to prove proof/verification machinery is actually driven by
+    /// `H` rather than hardcoding RFC 6962 SHA-256.
+    #[derive(Copy, Clone, Debug, Default)]
+    pub(crate) struct XorHasher;
+
+    impl Hasher for XorHasher {
+        fn empty_root() -> Hash {
+            Hash::default()
+        }
+
+        #[allow(clippy::indexing_slicing)] // fixed-size [u8; 32] buffers
+        fn hash_record(data: &[u8]) -> Hash {
+            let mut out = [0u8; 32];
+            for (i, &b) in data.iter().enumerate() {
+                out[i % 32] ^= b;
+            }
+            out[0] ^= 0x01;
+            Hash(out)
+        }
+
+        #[allow(clippy::indexing_slicing)] // fixed-size [u8; 32] buffers
+        fn hash_children(left: &Hash, right: &Hash) -> Hash {
+            let mut out = [0u8; 32];
+            for i in 0..32 {
+                out[i] = left.0[i] ^ right.0[i];
+            }
+            out[0] ^= 0x02;
+            Hash(out)
+        }
+    }
+
+    /// A [`ReadHash`] over a flat, in-memory array of stored hashes,
+    /// indexed by [`stored_hash_index`].
+    pub(crate) struct VecHashReader(pub(crate) Vec<Hash>);
+
+    impl ReadHash for VecHashReader {
+        type Error = core::convert::Infallible;
+        fn read_hashes(&self, indices: &[u64]) -> Result<impl Iterator<Item = Hash>, Self::Error> {
+            let hashes: Vec<Hash> = indices
+                .iter()
+                .map(|&i| self.0.get(i as usize).copied().unwrap_or_default())
+                .collect();
+            Ok(hashes.into_iter())
+        }
+    }
+
+    /// Builds the flat array of stored hashes for a log of `n`
+    /// records under hasher `H`, the same way a real implementation
+    /// would as records are appended.
+    #[allow(clippy::indexing_slicing)] // test-only incremental builder, indices are in-bounds by construction
+    pub(crate) fn build_hashes<H: Hasher>(n: u64) -> Vec<Hash> {
+        let mut hashes = vec![Hash::default(); stored_hash_count(n) as usize];
+        for i in 0..n {
+            hashes[stored_hash_index(0, i) as usize] = H::hash_record(&i.to_le_bytes());
+            let mut j = i;
+            let mut level = 0isize;
+            while j & 1 == 1 {
+                level += 1;
+                j >>= 1;
+                let left = hashes[stored_hash_index(level - 1, j * 2) as usize];
+                let right = hashes[stored_hash_index(level - 1, j * 2 + 1) as usize];
+                hashes[stored_hash_index(level, j) as usize] = H::hash_children(&left, &right);
+            }
+        }
+        hashes
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use test_support::{build_hashes, VecHashReader, XorHasher};
 
     #[test]
     fn test_split_stored_hash_index() {
@@ -182,4 +641,46 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    #[allow(clippy::unwrap_used)] // test-only, n and m are always in-range by construction
+    fn test_tree_hash_and_proofs() {
+        for n in 1..50 {
+            let reader = VecHashReader(build_hashes::<Rfc6962Sha256>(n));
+            let root = tree_hash(n, &reader).unwrap();
+
+            for i in 0..n {
+                let leaf_hash = record_hash(&i.to_le_bytes());
+                let proof = prove_record(n, i, &reader).unwrap();
+                assert!(check_record(&proof, n, i, leaf_hash, root), "record {i} of {n}");
+            }
+
+            for m in 1..=n {
+                let old_root = tree_hash(m, &reader).unwrap();
+                let proof = prove_tree(n, m, &reader).unwrap();
+                assert!(check_tree(&proof, n, m, old_root, root), "tree {m} of {n}");
+            }
+        }
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)] // test-only, n and m are always in-range by construction
+    fn test_custom_hasher() {
+        for n in 1..20 {
+            let reader = VecHashReader(build_hashes::<XorHasher>(n));
+            let root = tree_hash_with::<XorHasher, _>(n, &reader).unwrap();
+
+            for i in 0..n {
+                let leaf_hash = XorHasher::hash_record(&i.to_le_bytes());
+                let proof = prove_record_with::<XorHasher, _>(n, i, &reader).unwrap();
+                assert!(check_record_with::<XorHasher>(&proof, n, i, leaf_hash, root), "record {i} of {n}");
+            }
+
+            for m in 1..=n {
+                let old_root = tree_hash_with::<XorHasher, _>(m, &reader).unwrap();
+                let proof = prove_tree_with::<XorHasher, _>(n, m, &reader).unwrap();
+                assert!(check_tree_with::<XorHasher>(&proof, n, m, old_root, root), "tree {m} of {n}");
+            }
+        }
+    }
 }